@@ -1,19 +1,29 @@
+use chrono::{DateTime, Local};
 use clap::builder::PathBufValueParser;
 use clap::{arg, command, ArgAction};
 use std::path::PathBuf;
 
+use robocopy_logs_parser::{parse_datetime, FilesMode};
+
 pub struct Config {
-    pub source_file: PathBuf,
+    pub source: PathBuf,
     pub output_file: PathBuf,
     pub log_file: Option<PathBuf>,
     pub overwrite: bool,
+    pub since: Option<DateTime<Local>>,
+    pub until: Option<DateTime<Local>>,
+    pub files: FilesMode,
+    pub strict: bool,
+    pub exit_code: Option<u32>,
+    pub log_max_bytes: u64,
+    pub log_keep: usize,
 }
 
 impl Config {
     pub fn from_args() -> Self {
         let matches = command!()
             .arg(
-                arg!(--source <SOURCE> "Robocopy log file to process")
+                arg!(--source <SOURCE> "Robocopy log file, directory, or glob pattern to process")
                     .value_parser(PathBufValueParser::new())
                     .required(true),
             )
@@ -32,13 +42,76 @@ impl Config {
                     .takes_value(false)
                     .action(ArgAction::SetTrue),
             )
+            .arg(
+                arg!(--since <SINCE> "Only include runs started on or after this time (RFC3339 or Robocopy's own format)")
+                    .value_parser(parse_datetime_arg)
+                    .required(false),
+            )
+            .arg(
+                arg!(--until <UNTIL> "Only include runs started on or before this time (RFC3339 or Robocopy's own format)")
+                    .value_parser(parse_datetime_arg)
+                    .required(false),
+            )
+            .arg(
+                arg!(--files <MODE> "How much of the parsed file list to include: all, errors, or none")
+                    .value_parser(parse_files_mode_arg)
+                    .default_value("none")
+                    .required(false),
+            )
+            .arg(
+                arg!(--strict "Exit with an error if any log was parsed with diagnostics")
+                    .takes_value(false)
+                    .action(ArgAction::SetTrue),
+            )
+            .arg(
+                arg!(--"exit-code" <CODE> "Robocopy's own process exit code, to compute an outcome when the log doesn't record it")
+                    .value_parser(clap::value_parser!(u32))
+                    .required(false),
+            )
+            .arg(
+                arg!(--"log-max-bytes" <BYTES> "Roll the --log file once it would exceed this many bytes")
+                    .value_parser(clap::value_parser!(u64))
+                    .default_value("10485760")
+                    .required(false),
+            )
+            .arg(
+                arg!(--"log-keep" <COUNT> "How many rotated generations of the --log file to keep")
+                    .value_parser(clap::value_parser!(usize))
+                    .default_value("5")
+                    .required(false),
+            )
             .get_matches();
 
         Config {
-            source_file: matches.get_one::<PathBuf>("source").unwrap().clone(),
+            source: matches.get_one::<PathBuf>("source").unwrap().clone(),
             output_file: matches.get_one::<PathBuf>("output").unwrap().clone(),
             log_file: matches.get_one::<PathBuf>("log").cloned(),
             overwrite: matches.get_flag("overwrite"),
+            since: matches.get_one::<DateTime<Local>>("since").cloned(),
+            until: matches.get_one::<DateTime<Local>>("until").cloned(),
+            files: *matches.get_one::<FilesMode>("files").unwrap(),
+            strict: matches.get_flag("strict"),
+            exit_code: matches.get_one::<u32>("exit-code").copied(),
+            log_max_bytes: *matches.get_one::<u64>("log-max-bytes").unwrap(),
+            log_keep: *matches.get_one::<usize>("log-keep").unwrap(),
         }
     }
 }
+
+/// Adapts [`parse_datetime`] to clap's `value_parser` signature
+fn parse_datetime_arg(value: &str) -> Result<DateTime<Local>, String> {
+    parse_datetime(value).map_err(|err| err.to_string())
+}
+
+/// Parses the `--files` flag into a [`FilesMode`]
+fn parse_files_mode_arg(value: &str) -> Result<FilesMode, String> {
+    match value {
+        "all" => Ok(FilesMode::All),
+        "errors" => Ok(FilesMode::Errors),
+        "none" => Ok(FilesMode::None),
+        _ => Err(format!(
+            "Invalid value `{}`: expected `all`, `errors`, or `none`",
+            value
+        )),
+    }
+}
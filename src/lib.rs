@@ -0,0 +1,921 @@
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, TimeZone};
+use encoding_rs_io::DecodeReaderBytesBuilder;
+use flate2::read::GzDecoder;
+use serde::Serialize;
+use std::fmt::Debug;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Cursor, Read};
+use std::path::Path;
+use tracing::{instrument, warn};
+use zstd::stream::read::Decoder as ZstdDecoder;
+
+static DATE_TIME_FORMAT: &str = "%A, %B %e, %Y %r";
+
+/// How a file's bytes are compressed on disk, if at all
+enum CompressionKind {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl CompressionKind {
+    /// Sniff the compression format from the first bytes of a file
+    fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0x1f, 0x8b]) {
+            CompressionKind::Gzip
+        } else if bytes.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            CompressionKind::Zstd
+        } else {
+            CompressionKind::None
+        }
+    }
+}
+
+/// Which text encoding a log is stored in
+enum DecoderKind {
+    Utf16Le,
+    Utf16Be,
+    Utf8,
+}
+
+impl DecoderKind {
+    /// Sniff the text encoding from the first bytes of a (decompressed) file
+    ///
+    /// Robocopy writes UTF-16 (with a BOM) when run with `/UNICODE`, and plain
+    /// ANSI/UTF-8 otherwise, so a BOM is checked for first, falling back to a
+    /// heuristic for BOM-less UTF-16LE before assuming UTF-8
+    fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&[0xFF, 0xFE]) {
+            DecoderKind::Utf16Le
+        } else if bytes.starts_with(&[0xFE, 0xFF]) {
+            DecoderKind::Utf16Be
+        } else if bytes.starts_with(&[0xEF, 0xBB, 0xBF]) {
+            DecoderKind::Utf8
+        } else if looks_like_bare_utf16le(bytes) {
+            DecoderKind::Utf16Le
+        } else {
+            DecoderKind::Utf8
+        }
+    }
+
+    fn encoding(&self) -> &'static encoding_rs::Encoding {
+        match self {
+            DecoderKind::Utf16Le => encoding_rs::UTF_16LE,
+            DecoderKind::Utf16Be => encoding_rs::UTF_16BE,
+            DecoderKind::Utf8 => encoding_rs::UTF_8,
+        }
+    }
+}
+
+/// Whether `bytes` look like BOM-less UTF-16LE, i.e. mostly nulls on odd
+/// positions, as produced by interleaving ASCII characters with `0x00`
+fn looks_like_bare_utf16le(bytes: &[u8]) -> bool {
+    if bytes.len() < 4 {
+        return false;
+    }
+    let odd_zero_count = bytes.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+    let odd_count = bytes.len() / 2;
+    odd_zero_count * 2 > odd_count
+}
+
+/// Peek at the first `len` bytes of `reader` without losing them, by
+/// prepending them back via a [`Cursor`] once sniffing is done
+fn peek<R: Read>(mut reader: R, len: usize) -> Result<(Vec<u8>, impl Read)> {
+    let mut buf = vec![0u8; len];
+    let mut read = 0;
+    while read < len {
+        let n = reader.read(&mut buf[read..])?;
+        if n == 0 {
+            break;
+        }
+        read += n;
+    }
+    buf.truncate(read);
+    let peeked = buf.clone();
+    Ok((peeked, Cursor::new(buf).chain(reader)))
+}
+
+/// Wrap `file` in a decompressor if it looks gzip- or zstd-compressed
+fn decompress(file: File) -> Result<Box<dyn Read>> {
+    let (peeked, chained) = peek(file, 4)?;
+
+    Ok(match CompressionKind::detect(&peeked) {
+        CompressionKind::Gzip => Box::new(GzDecoder::new(chained)),
+        CompressionKind::Zstd => {
+            Box::new(ZstdDecoder::new(chained).context("Failed to initialize zstd decoder")?)
+        }
+        CompressionKind::None => Box::new(chained),
+    })
+}
+
+/// Pick a text decoder for `reader` based on a sniff of its first bytes
+fn build_decoder(reader: Box<dyn Read>) -> Result<impl Read> {
+    let (peeked, chained) = peek(reader, 8)?;
+    let kind = DecoderKind::detect(&peeked);
+
+    Ok(DecodeReaderBytesBuilder::new()
+        .encoding(Some(kind.encoding()))
+        .build(chained))
+}
+
+/// A single entry from the file list section of the log
+#[derive(Debug, Serialize)]
+pub struct FileEntry {
+    /// The status token Robocopy printed, e.g. `New File`, `*EXTRA File`, or `ERROR`
+    status: String,
+    size: Option<u128>,
+    path: String,
+    /// Win32 error code, for `ERROR` entries
+    error_code: Option<u32>,
+    /// The continuation line following an `ERROR` entry, if any
+    error_message: Option<String>,
+}
+
+/// Status tokens Robocopy prints in the file list section; none is a prefix
+/// of another, so the order they are tried in does not matter
+static KNOWN_FILE_STATUSES: &[&str] = &[
+    "*EXTRA File",
+    "*EXTRA Dir",
+    "*Mismatch",
+    "New File",
+    "Newer",
+    "Older",
+    "Changed",
+    "Tweaked",
+    "Same",
+];
+
+/// Parse a non-error line from the file list section, e.g.
+/// `New File          1234    C:\path\to\file.txt`
+///
+/// Directory entries (e.g. `*EXTRA Dir`) carry no size, so the leading
+/// numeric token is only consumed when present; otherwise the whole
+/// remainder is the path
+fn parse_file_entry_line(line: &str) -> Option<FileEntry> {
+    KNOWN_FILE_STATUSES.iter().find_map(|status| {
+        let rest = line.strip_prefix(status)?.trim_start();
+        let (size, path) = match rest.split_once(char::is_whitespace) {
+            Some((size, path)) if size.parse::<u128>().is_ok() => {
+                (size.parse().ok(), path.trim().to_string())
+            }
+            _ => (None, rest.trim().to_string()),
+        };
+        Some(FileEntry {
+            status: status.to_string(),
+            size,
+            path,
+            error_code: None,
+            error_message: None,
+        })
+    })
+}
+
+/// Action phrases Robocopy prints between the `(0xHEX)` code and the path on
+/// an `ERROR` line; none is a prefix of another, so the order they are tried
+/// in does not matter
+static KNOWN_ERROR_ACTIONS: &[&str] = &[
+    "Creating Destination Directory",
+    "Accessing Destination Directory",
+    "Accessing Source Directory",
+    "Accessing Destination File",
+    "Accessing Source File",
+    "Changing File Attributes",
+    "Removing Extra Dir",
+    "Removing Extra File",
+    "Creating File",
+    "Copying File",
+    "Copying Dir",
+];
+
+/// Parse an error line from the file list section, e.g.
+/// `ERROR 5 (0x00000005) Copying File C:\path\to\file.txt`
+fn parse_error_line(line: &str) -> Option<FileEntry> {
+    let rest = line.strip_prefix("ERROR ")?;
+    let (code, rest) = rest.split_once(' ')?;
+    let error_code = code.parse().ok()?;
+
+    let rest = rest.trim_start().strip_prefix('(')?;
+    let (_hex_code, rest) = rest.split_once(')')?;
+    let rest = rest.trim_start();
+
+    // The remainder looks like "<action phrase> <path>"; fall back to the
+    // whole remainder if the action phrase isn't one we recognize
+    let path = KNOWN_ERROR_ACTIONS
+        .iter()
+        .find_map(|action| rest.strip_prefix(action))
+        .unwrap_or(rest)
+        .trim_start()
+        .to_string();
+
+    Some(FileEntry {
+        status: "ERROR".to_string(),
+        size: None,
+        path,
+        error_code: Some(error_code),
+        error_message: None,
+    })
+}
+
+#[derive(Debug, Serialize)]
+pub struct CopyStat {
+    total: u128,
+    copied: u128,
+    skipped: u128,
+    mismatch: u128,
+    failed: u128,
+    extras: u128,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    dirs: Option<CopyStat>,
+    files: Option<CopyStat>,
+    bytes: Option<CopyStat>,
+}
+
+/// What kind of problem was encountered while parsing a line
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParseErrorKind {
+    UnknownKey,
+    InvalidValue,
+}
+
+/// A single problem encountered while parsing the header or footer section
+#[derive(Debug, Serialize)]
+pub struct ParseError {
+    line_no: usize,
+    section: &'static str,
+    key: String,
+    kind: ParseErrorKind,
+    message: String,
+}
+
+/// Robocopy's own interpretation of its bitmask exit code
+///
+/// Bit 1 = files copied, bit 2 = extra files/dirs, bit 4 = mismatches,
+/// bit 8 = copy failures, bit 16 = fatal error; 0 means nothing to do
+#[derive(Debug, Serialize)]
+pub struct Outcome {
+    files_copied: bool,
+    had_extras: bool,
+    had_mismatches: bool,
+    had_failures: bool,
+    fatal_error: bool,
+    /// `false` when a copy failure or a fatal error bit is set
+    ok: bool,
+}
+
+impl Outcome {
+    /// `false` when a copy failure or a fatal error bit is set
+    pub fn ok(&self) -> bool {
+        self.ok
+    }
+
+    fn from_exit_code(code: u32) -> Self {
+        let had_failures = code & 0x8 != 0;
+        let fatal_error = code & 0x10 != 0;
+        Outcome {
+            files_copied: code & 0x1 != 0,
+            had_extras: code & 0x2 != 0,
+            had_mismatches: code & 0x4 != 0,
+            had_failures,
+            fatal_error,
+            ok: !(had_failures || fatal_error),
+        }
+    }
+}
+
+#[derive(Default, Debug, Serialize)]
+pub struct RobocopyResult {
+    started: Option<DateTime<Local>>,
+    ended: Option<DateTime<Local>>,
+    source: Option<String>,
+    destination: Option<String>,
+    /// Files selection pattern
+    files: Option<String>,
+    options: Option<String>,
+    /// In bytes per second
+    speed: Option<u128>,
+    stats: Stats,
+    file_entries: Vec<FileEntry>,
+    diagnostics: Vec<ParseError>,
+    exit_code: Option<u32>,
+    outcome: Option<Outcome>,
+}
+
+/// How much of the parsed file list [`RobocopyResult::file_entries`] to keep
+#[derive(Debug, Clone, Copy)]
+pub enum FilesMode {
+    All,
+    Errors,
+    None,
+}
+
+impl RobocopyResult {
+    /// Read and parse the file into a usable struct
+    ///
+    /// Transparently decompresses gzip/zstd-compressed files and detects
+    /// the text encoding before handing the decoded lines to [`Self::from_reader`]
+    #[instrument]
+    pub fn read_file<P: AsRef<Path> + Debug>(path: P) -> Result<Self> {
+        let file = File::open(path)?;
+
+        let decompressed = decompress(file)?;
+        let decoder = build_decoder(decompressed)?;
+        let buffered_file = BufReader::new(decoder);
+
+        Self::from_reader(buffered_file)
+    }
+
+    /// Parse an already-decoded Robocopy log from any buffered reader, e.g.
+    /// a subprocess's stdout, a network stream, or an in-memory buffer
+    pub fn from_reader<R: BufRead>(reader: R) -> Result<Self> {
+        let mut robocopy_result = Self::default();
+
+        // There are four sections, each coming after a first line of only dashes:
+        // 1. ROBOCOPY title
+        // 2. Initial info and config
+        // 3. Files list
+        // 4. End statistics
+        let mut section = 0;
+        let mut saw_robocopy_title = false;
+        let mut awaiting_error_message = false;
+
+        for (line_no, line) in reader.lines().enumerate() {
+            let line = match line {
+                Ok(line) => line,
+                Err(err) => {
+                    warn!("Failed to read line {}: {}", line_no, err);
+                    continue;
+                }
+            };
+
+            let line = line.trim();
+
+            if line.is_empty() {
+                continue;
+            }
+
+            if line.trim_start_matches('-').is_empty() {
+                if section == 1 && !saw_robocopy_title {
+                    return Err(anyhow!("Does not look like a Robocopy log: missing title"));
+                }
+                section += 1;
+                awaiting_error_message = false;
+                continue;
+            }
+
+            if section == 1 && line.contains("ROBOCOPY") {
+                saw_robocopy_title = true;
+            } else if section == 2 {
+                if let Some((k, v)) = split_key_value(line) {
+                    robocopy_result.parse_header(line_no, k, v);
+                }
+            } else if section == 3 {
+                if let Some(entry) = parse_error_line(line) {
+                    robocopy_result.file_entries.push(entry);
+                    awaiting_error_message = true;
+                } else if awaiting_error_message {
+                    if let Some(entry) = robocopy_result.file_entries.last_mut() {
+                        entry.error_message = Some(line.to_string());
+                    }
+                    awaiting_error_message = false;
+                } else if let Some(entry) = parse_file_entry_line(line) {
+                    robocopy_result.file_entries.push(entry);
+                }
+            } else if section == 4 {
+                if let Some((k, v)) = split_key_value(line) {
+                    robocopy_result.parse_footer(line_no, k, v);
+                }
+            }
+        }
+
+        if !saw_robocopy_title {
+            return Err(anyhow!("Does not look like a Robocopy log: missing title"));
+        }
+
+        Ok(robocopy_result)
+    }
+
+    /// Time the run started, if it could be parsed from the header
+    pub fn started(&self) -> Option<DateTime<Local>> {
+        self.started
+    }
+
+    /// Time the run ended, if it could be parsed from the footer
+    pub fn ended(&self) -> Option<DateTime<Local>> {
+        self.ended
+    }
+
+    /// Problems encountered while parsing the header and footer sections
+    pub fn diagnostics(&self) -> &[ParseError] {
+        &self.diagnostics
+    }
+
+    /// Robocopy's own judgement of whether the run succeeded, if its exit
+    /// code is known
+    pub fn outcome(&self) -> Option<&Outcome> {
+        self.outcome.as_ref()
+    }
+
+    /// Record Robocopy's process exit code and derive [`Self::outcome`] from it
+    ///
+    /// Robocopy itself never writes its own exit code into the log, so this
+    /// must come from the caller: either the `--exit-code` flag, or a
+    /// trailing `Exit Code: N` / `Return Code: N` line some wrapper scripts
+    /// append after the footer
+    pub fn set_exit_code(&mut self, code: u32) {
+        self.exit_code = Some(code);
+        self.outcome = Some(Outcome::from_exit_code(code));
+    }
+
+    /// Drop file entries not wanted in the output, per `--files`
+    pub fn retain_file_entries(&mut self, mode: FilesMode) {
+        match mode {
+            FilesMode::All => {}
+            FilesMode::Errors => self.file_entries.retain(|entry| entry.error_code.is_some()),
+            FilesMode::None => self.file_entries.clear(),
+        }
+    }
+
+    /// Parse a header key and value
+    ///
+    /// Expects the keys and values to be trimmed
+    ///
+    /// Possible fields:
+    /// * Started
+    /// * Source
+    /// * Dest
+    /// * Files
+    /// * Options
+    ///
+    /// Any problem is recorded in [`Self::diagnostics`] rather than returned
+    #[instrument(skip(self))]
+    pub fn parse_header(&mut self, line_no: usize, key: &str, value: &str) {
+        let outcome: Result<(), (ParseErrorKind, String)> = (|| {
+            match key {
+                "Started" => {
+                    self.started = Some(
+                        Local
+                            .datetime_from_str(value, DATE_TIME_FORMAT)
+                            .map_err(|err| (ParseErrorKind::InvalidValue, err.to_string()))?,
+                    )
+                }
+                "Source" => self.source = Some(value.to_string()),
+                "Dest" => self.destination = Some(value.to_string()),
+                "Files" => self.files = Some(value.to_string()),
+                "Options" => self.options = Some(value.to_string()),
+                _ => {
+                    return Err((
+                        ParseErrorKind::UnknownKey,
+                        format!("Unknown header key: {}", key),
+                    ))
+                }
+            };
+            Ok(())
+        })();
+
+        if let Err((kind, message)) = outcome {
+            self.diagnostics.push(ParseError {
+                line_no,
+                section: "header",
+                key: key.to_string(),
+                kind,
+                message,
+            });
+        }
+    }
+
+    /// Parse a footer key and value
+    ///
+    /// Expects the keys and values to be trimmed
+    ///
+    /// Possible fields:
+    /// * Ended
+    /// * Speed (bytes only)
+    /// * Dirs
+    /// * Files
+    /// * Bytes
+    /// * Exit Code / ExitCode / Return Code / ReturnCode
+    ///
+    /// Any problem is recorded in [`Self::diagnostics`] rather than returned
+    #[instrument(skip(self))]
+    pub fn parse_footer(&mut self, line_no: usize, key: &str, value: &str) {
+        let outcome: Result<(), (ParseErrorKind, String)> = (|| {
+            match key {
+                "Ended" => {
+                    self.ended = Some(
+                        Local
+                            .datetime_from_str(value, DATE_TIME_FORMAT)
+                            .map_err(|err| (ParseErrorKind::InvalidValue, err.to_string()))?,
+                    )
+                }
+                "Speed" => {
+                    self.speed = Some(
+                        parse_speed(value)
+                            .map_err(|err| (ParseErrorKind::InvalidValue, err.to_string()))?,
+                    )
+                }
+                "Dirs" => {
+                    self.stats.dirs = Some(
+                        parse_stats(value)
+                            .map_err(|err| (ParseErrorKind::InvalidValue, err.to_string()))?,
+                    )
+                }
+                "Files" => {
+                    self.stats.files = Some(
+                        parse_stats(value)
+                            .map_err(|err| (ParseErrorKind::InvalidValue, err.to_string()))?,
+                    )
+                }
+                "Bytes" => {
+                    self.stats.bytes = Some(
+                        parse_stats(value)
+                            .map_err(|err| (ParseErrorKind::InvalidValue, err.to_string()))?,
+                    )
+                }
+                // Robocopy itself never prints this; some wrapper scripts
+                // append the process exit code as a trailing footer line
+                "Exit Code" | "ExitCode" | "Return Code" | "ReturnCode" => {
+                    let code = value
+                        .trim()
+                        .parse()
+                        .map_err(|err: std::num::ParseIntError| {
+                            (ParseErrorKind::InvalidValue, err.to_string())
+                        })?;
+                    self.set_exit_code(code);
+                }
+                _ => {
+                    return Err((
+                        ParseErrorKind::UnknownKey,
+                        format!("Unknown footer key: {}", key),
+                    ))
+                }
+            };
+            Ok(())
+        })();
+
+        if let Err((kind, message)) = outcome {
+            self.diagnostics.push(ParseError {
+                line_no,
+                section: "footer",
+                key: key.to_string(),
+                kind,
+                message,
+            });
+        };
+    }
+}
+
+/// Write a batch of parsed results to the output file as a single JSON array
+#[instrument(skip(results))]
+pub fn write_results<P: AsRef<Path> + Debug>(
+    results: &[RobocopyResult],
+    output: P,
+    overwrite: bool,
+) -> Result<()> {
+    let mut options = File::options();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    let file = options.open(output).context("Failed to open output file")?;
+    serde_json::to_writer(&file, results).context("Failed to write output file")
+}
+
+/// Parse a timestamp given either as RFC3339 or in Robocopy's own log format
+pub fn parse_datetime(value: &str) -> Result<DateTime<Local>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Ok(dt.with_timezone(&Local));
+    }
+    Local
+        .datetime_from_str(value, DATE_TIME_FORMAT)
+        .context("Failed to parse timestamp")
+}
+
+/// Convert a header or footer line in Key: Value
+/// Keys are the characters until the first `:`, Values are the rest of the line
+/// Both keys and values are returned trimmed
+fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    line.split_once(':').map(|(k, v)| (k.trim(), v.trim()))
+}
+
+fn parse_speed(value: &str) -> Result<u128> {
+    let (value, unit) = value
+        .split_once(' ')
+        .ok_or_else(|| anyhow!("Unrecognized speed value: {}", value))?;
+    if unit.eq_ignore_ascii_case("Bytes/sec.") {
+        value.parse().context("Failed to parse speed value")
+    } else {
+        Err(anyhow!("Unexpected speed unit: {}", unit))
+    }
+}
+
+/// Parses the copy statistics from the Robocopy log
+fn parse_stats(value: &str) -> Result<CopyStat> {
+    let fields_iter = value.split_ascii_whitespace();
+    let field_vec = fields_iter.collect::<Vec<&str>>();
+    let field_count = field_vec.len();
+    if field_count != 6 {
+        return Err(anyhow!(
+            "Unexpected number of fields: {} instead of 6",
+            field_count,
+        ));
+    };
+    Ok(CopyStat {
+        total: field_vec[0].parse()?,
+        copied: field_vec[1].parse()?,
+        skipped: field_vec[2].parse()?,
+        mismatch: field_vec[3].parse()?,
+        failed: field_vec[4].parse()?,
+        extras: field_vec[5].parse()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    #[test]
+    fn compression_kind_detects_gzip_and_zstd_magic_bytes() {
+        assert!(matches!(
+            CompressionKind::detect(&[0x1f, 0x8b, 0x08, 0x00]),
+            CompressionKind::Gzip
+        ));
+        assert!(matches!(
+            CompressionKind::detect(&[0x28, 0xb5, 0x2f, 0xfd]),
+            CompressionKind::Zstd
+        ));
+        assert!(matches!(
+            CompressionKind::detect(b"plain text"),
+            CompressionKind::None
+        ));
+    }
+
+    #[test]
+    fn decoder_kind_detects_boms_and_falls_back_to_utf8() {
+        assert!(matches!(
+            DecoderKind::detect(&[0xFF, 0xFE, b'a', 0x00]),
+            DecoderKind::Utf16Le
+        ));
+        assert!(matches!(
+            DecoderKind::detect(&[0xFE, 0xFF, 0x00, b'a']),
+            DecoderKind::Utf16Be
+        ));
+        assert!(matches!(
+            DecoderKind::detect(&[0xEF, 0xBB, 0xBF, b'a']),
+            DecoderKind::Utf8
+        ));
+        assert!(matches!(
+            DecoderKind::detect(b"plain ascii text"),
+            DecoderKind::Utf8
+        ));
+    }
+
+    #[test]
+    fn decoder_kind_detects_bare_utf16le_without_a_bom() {
+        // ASCII interleaved with 0x00, as Robocopy writes without a BOM on some systems
+        let bare_utf16le: Vec<u8> = "ROBOCOPY"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        assert!(matches!(
+            DecoderKind::detect(&bare_utf16le),
+            DecoderKind::Utf16Le
+        ));
+    }
+
+    #[test]
+    fn looks_like_bare_utf16le_requires_mostly_null_odd_bytes() {
+        let bare_utf16le: Vec<u8> = "ROBOCOPY"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        assert!(looks_like_bare_utf16le(&bare_utf16le));
+        assert!(!looks_like_bare_utf16le(b"plain ascii text"));
+        assert!(!looks_like_bare_utf16le(b"ab"));
+    }
+
+    #[test]
+    fn peek_preserves_bytes_for_the_rest_of_the_read() {
+        let (peeked, mut chained) = peek(Cursor::new(b"hello world".to_vec()), 5).unwrap();
+        assert_eq!(peeked, b"hello");
+
+        let mut rest = Vec::new();
+        chained.read_to_end(&mut rest).unwrap();
+        assert_eq!(rest, b"hello world");
+    }
+
+    #[test]
+    fn peek_handles_input_shorter_than_the_requested_length() {
+        let (peeked, _chained) = peek(Cursor::new(b"hi".to_vec()), 8).unwrap();
+        assert_eq!(peeked, b"hi");
+    }
+
+    fn temp_file(name: &str, contents: &[u8]) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!(
+            "robocopy-lib-test-{}-{}",
+            uuid::Uuid::new_v4(),
+            name
+        ));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    #[test]
+    fn decompress_passes_through_uncompressed_files() {
+        let path = temp_file("plain.log", b"hello world");
+        let file = File::open(&path).unwrap();
+        let mut decoded = String::new();
+        decompress(file)
+            .unwrap()
+            .read_to_string(&mut decoded)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(decoded, "hello world");
+    }
+
+    #[test]
+    fn decompress_inflates_gzip_files() {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(b"hello world").unwrap();
+        let gzipped = encoder.finish().unwrap();
+
+        let path = temp_file("gzipped.log", &gzipped);
+        let file = File::open(&path).unwrap();
+        let mut decoded = String::new();
+        decompress(file)
+            .unwrap()
+            .read_to_string(&mut decoded)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(decoded, "hello world");
+    }
+
+    #[test]
+    fn decompress_decodes_zstd_files() {
+        let zstd_bytes = zstd::stream::encode_all(Cursor::new(b"hello world".to_vec()), 0).unwrap();
+
+        let path = temp_file("zstd.log", &zstd_bytes);
+        let file = File::open(&path).unwrap();
+        let mut decoded = String::new();
+        decompress(file)
+            .unwrap()
+            .read_to_string(&mut decoded)
+            .unwrap();
+        std::fs::remove_file(&path).ok();
+        assert_eq!(decoded, "hello world");
+    }
+
+    #[test]
+    fn build_decoder_converts_utf16le_with_bom_to_utf8() {
+        let mut bytes = vec![0xFF, 0xFE];
+        bytes.extend("hello".encode_utf16().flat_map(u16::to_le_bytes));
+
+        let mut decoded = String::new();
+        build_decoder(Box::new(Cursor::new(bytes)))
+            .unwrap()
+            .read_to_string(&mut decoded)
+            .unwrap();
+        assert_eq!(decoded, "hello");
+    }
+
+    const SAMPLE_LOG: &str = "\
+-------------------------------------------------------------------------------
+   ROBOCOPY     ::     Robust File Copy for Windows
+-------------------------------------------------------------------------------
+
+  Started : Monday, January 5, 2026 10:00:00 AM
+   Source : C:\\src\\
+     Dest : C:\\dst\\
+
+    Files : *.*
+
+  Options : *.* /S /E /DCOPY:DA /COPY:DAT /R:1000000 /W:30
+-------------------------------------------------------------------------------
+-------------------------------------------------------------------------------
+                   Total    Copied   Skipped  Mismatch    FAILED    Extras
+    Dirs :         1         1         0         0         0         0
+   Files :         2         1         0         0         1         1
+   Bytes :         2         1         0         0         1         1
+
+   Speed :              123456 Bytes/sec.
+    Ended : Monday, January 5, 2026 10:00:10 AM
+";
+
+    fn parse(log: &str) -> Result<RobocopyResult> {
+        RobocopyResult::from_reader(Cursor::new(log.as_bytes()))
+    }
+
+    #[test]
+    fn from_reader_parses_header_and_footer() {
+        let result = parse(SAMPLE_LOG).unwrap();
+        assert!(result.started().is_some());
+        assert!(result.ended().is_some());
+        assert_eq!(result.diagnostics().len(), 0);
+    }
+
+    #[test]
+    fn from_reader_rejects_a_file_with_no_separator_lines() {
+        let err = parse("this is not a robocopy log\n").unwrap_err();
+        assert!(err.to_string().contains("missing title"));
+    }
+
+    #[test]
+    fn from_reader_rejects_an_empty_file() {
+        let err = parse("").unwrap_err();
+        assert!(err.to_string().contains("missing title"));
+    }
+
+    #[test]
+    fn from_reader_rejects_a_file_with_a_separator_but_no_title() {
+        let log = "-------------------------------------------------------------------------------\nnot robocopy\n-------------------------------------------------------------------------------\n";
+        let err = parse(log).unwrap_err();
+        assert!(err.to_string().contains("missing title"));
+    }
+
+    #[test]
+    fn parse_error_line_handles_two_word_action_phrases() {
+        let entry = parse_error_line("ERROR 5 (0x00000005) Copying File C:\\src\\a.txt").unwrap();
+        assert_eq!(entry.path, "C:\\src\\a.txt");
+        assert_eq!(entry.error_code, Some(5));
+    }
+
+    #[test]
+    fn parse_error_line_handles_three_word_action_phrases() {
+        let entry =
+            parse_error_line("ERROR 5 (0x00000005) Accessing Source Directory C:\\src\\locked\\")
+                .unwrap();
+        assert_eq!(entry.path, "C:\\src\\locked\\");
+    }
+
+    #[test]
+    fn parse_file_entry_line_splits_status_size_and_path() {
+        let entry =
+            parse_file_entry_line("New File          1234    C:\\path\\to\\file.txt").unwrap();
+        assert_eq!(entry.status, "New File");
+        assert_eq!(entry.size, Some(1234));
+        assert_eq!(entry.path, "C:\\path\\to\\file.txt");
+    }
+
+    #[test]
+    fn parse_file_entry_line_handles_sizeless_directory_entries() {
+        let entry = parse_file_entry_line("*EXTRA Dir                C:\\dst\\extra\\").unwrap();
+        assert_eq!(entry.status, "*EXTRA Dir");
+        assert_eq!(entry.size, None);
+        assert_eq!(entry.path, "C:\\dst\\extra\\");
+    }
+
+    #[test]
+    fn parse_header_records_a_diagnostic_for_an_unknown_key() {
+        let mut result = RobocopyResult::default();
+        result.parse_header(5, "Bogus", "whatever");
+
+        assert_eq!(result.diagnostics().len(), 1);
+        let diagnostic = &result.diagnostics()[0];
+        assert_eq!(diagnostic.line_no, 5);
+        assert_eq!(diagnostic.section, "header");
+        assert_eq!(diagnostic.key, "Bogus");
+        assert!(matches!(diagnostic.kind, ParseErrorKind::UnknownKey));
+    }
+
+    #[test]
+    fn parse_footer_records_a_diagnostic_for_an_invalid_value() {
+        let mut result = RobocopyResult::default();
+        result.parse_footer(12, "Speed", "not a speed value");
+
+        assert_eq!(result.diagnostics().len(), 1);
+        let diagnostic = &result.diagnostics()[0];
+        assert_eq!(diagnostic.line_no, 12);
+        assert_eq!(diagnostic.section, "footer");
+        assert_eq!(diagnostic.key, "Speed");
+        assert!(matches!(diagnostic.kind, ParseErrorKind::InvalidValue));
+    }
+
+    #[test]
+    fn parse_header_and_footer_leave_no_diagnostic_for_known_keys() {
+        let mut result = RobocopyResult::default();
+        result.parse_header(1, "Source", "C:\\src\\");
+        result.parse_footer(2, "Speed", "123 Bytes/sec.");
+
+        assert_eq!(result.diagnostics().len(), 0);
+    }
+
+    #[test]
+    fn outcome_from_exit_code_decodes_the_bitmask() {
+        let outcome = Outcome::from_exit_code(0x8);
+        assert!(!outcome.ok());
+
+        let outcome = Outcome::from_exit_code(0x1);
+        assert!(outcome.ok());
+
+        let outcome = Outcome::from_exit_code(0x10);
+        assert!(!outcome.ok());
+    }
+}
@@ -0,0 +1,126 @@
+use anyhow::{Context, Result};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+/// A [`Write`] implementation that rolls the underlying file to a numbered
+/// sibling once it would exceed a byte cap, keeping at most a fixed number
+/// of rotated generations, so a long-running process's own log doesn't grow
+/// without bound
+pub struct RotatingWriter {
+    path: PathBuf,
+    max_bytes: u64,
+    keep: usize,
+    file: File,
+    written: u64,
+}
+
+impl RotatingWriter {
+    pub fn new(path: PathBuf, max_bytes: u64, keep: usize) -> Result<Self> {
+        let file = Self::open(&path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path,
+            max_bytes,
+            keep,
+            file,
+            written,
+        })
+    }
+
+    fn open(path: &PathBuf) -> Result<File> {
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open log file {:?}", path))
+    }
+
+    /// Roll `<path>` to `<path>.1`, shifting existing generations up to
+    /// `<path>.<keep>` and dropping whatever falls past that, then start a
+    /// fresh, empty file at `<path>`
+    fn rotate(&mut self) -> Result<()> {
+        if self.keep == 0 {
+            self.file = OpenOptions::new()
+                .create(true)
+                .write(true)
+                .truncate(true)
+                .open(&self.path)
+                .with_context(|| format!("Failed to truncate log file {:?}", self.path))?;
+            self.written = 0;
+            return Ok(());
+        }
+
+        for generation in (1..self.keep).rev() {
+            let from = self.generation_path(generation);
+            if from.exists() {
+                let to = self.generation_path(generation + 1);
+                std::fs::rename(&from, &to)
+                    .with_context(|| format!("Failed to rotate {:?} to {:?}", from, to))?;
+            }
+        }
+
+        let first = self.generation_path(1);
+        std::fs::rename(&self.path, &first)
+            .with_context(|| format!("Failed to rotate {:?} to {:?}", self.path, first))?;
+
+        self.file = Self::open(&self.path)?;
+        self.written = 0;
+        Ok(())
+    }
+
+    fn generation_path(&self, generation: usize) -> PathBuf {
+        let mut name = self.path.clone().into_os_string();
+        name.push(format!(".{}", generation));
+        PathBuf::from(name)
+    }
+}
+
+impl Write for RotatingWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.written > 0 && self.written + buf.len() as u64 > self.max_bytes {
+            self.rotate().map_err(std::io::Error::other)?;
+        }
+        let n = self.file.write(buf)?;
+        self.written += n as u64;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.file.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_path() -> PathBuf {
+        std::env::temp_dir().join(format!(
+            "robocopy-log-rotation-test-{}",
+            uuid::Uuid::new_v4()
+        ))
+    }
+
+    #[test]
+    fn rotates_and_keeps_at_most_the_configured_generations() {
+        let path = temp_path();
+        let mut writer = RotatingWriter::new(path.clone(), 10, 2).unwrap();
+
+        for _ in 0..5 {
+            writer.write_all(b"0123456789").unwrap();
+        }
+
+        assert!(path.exists());
+        let gen1 = PathBuf::from(format!("{}.1", path.display()));
+        let gen2 = PathBuf::from(format!("{}.2", path.display()));
+        let gen3 = PathBuf::from(format!("{}.3", path.display()));
+        assert!(gen1.exists());
+        assert!(gen2.exists());
+        assert!(!gen3.exists());
+
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(&gen1).ok();
+        std::fs::remove_file(&gen2).ok();
+    }
+}
@@ -1,17 +1,22 @@
-use anyhow::Result;
-use chrono::Utc;
-use std::fs::OpenOptions;
-use tracing::{error, info, instrument};
+use anyhow::{anyhow, Context, Result};
+use chrono::{DateTime, Local, Utc};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tracing::{error, info, instrument, warn};
 use tracing_subscriber::layer::SubscriberExt;
 use tracing_subscriber::util::SubscriberInitExt;
 
-use robocopy::RobocopyResult;
+use robocopy_logs_parser::RobocopyResult;
 use uuid::Uuid;
 
 use crate::config::Config;
+use crate::log_rotation::RotatingWriter;
 
 mod config;
-mod robocopy;
+mod log_rotation;
+
+/// How often to log a progress update while processing a batch of sources
+static PROGRESS_INTERVAL: usize = 100;
 
 fn main() -> Result<()> {
     let config = Config::from_args();
@@ -21,27 +26,89 @@ fn main() -> Result<()> {
     let subscriber = tracing_subscriber::Registry::default().with(log_stdout);
 
     let log_json = if let Some(log_file_path) = &config.log_file {
-        let file = OpenOptions::new()
-            .write(true)
-            .create(true)
-            .append(true)
-            .open(log_file_path)?;
+        let writer =
+            RotatingWriter::new(log_file_path.clone(), config.log_max_bytes, config.log_keep)?;
 
-        Some(tracing_subscriber::fmt::layer().json().with_writer(file))
+        Some(
+            tracing_subscriber::fmt::layer()
+                .json()
+                .with_writer(Mutex::new(writer)),
+        )
     } else {
         None
     };
     subscriber.with(log_json).init();
-    work(&config);
+    if !work(&config) {
+        std::process::exit(1);
+    }
     Ok(())
 }
 
+/// Runs the conversion, returning whether it succeeded
 #[instrument(skip_all, name = "main", fields(execution_id = Uuid::new_v4().to_string()))]
-fn work(config: &Config) {
+fn work(config: &Config) -> bool {
     let start_time = Utc::now();
     let result = (|| {
-        let r = RobocopyResult::read_file(&config.source_file)?;
-        r.write_to_file(&config.output_file, config.overwrite)?;
+        let sources = collect_sources(&config.source)?;
+        let total = sources.len();
+        let mut results = Vec::with_capacity(total);
+
+        // --exit-code is the caller's process exit code for a single
+        // Robocopy invocation, so it can't be meaningfully stamped onto
+        // every file of a directory/glob batch
+        let exit_code = if total > 1 {
+            if config.exit_code.is_some() {
+                warn!(
+                    "Ignoring --exit-code: it only applies to a single source file, not a batch of {}",
+                    total
+                );
+            }
+            None
+        } else {
+            config.exit_code
+        };
+
+        for (index, path) in sources.iter().enumerate() {
+            match RobocopyResult::read_file(path) {
+                Ok(mut r) if in_window(&r, config.since, config.until) => {
+                    r.retain_file_entries(config.files);
+                    if let Some(code) = exit_code {
+                        r.set_exit_code(code);
+                    }
+                    results.push(r);
+                }
+                Ok(_) => {}
+                Err(err) => warn!(file = %path.display(), "Skipping file: {}", err),
+            }
+
+            if (index + 1) % PROGRESS_INTERVAL == 0 || index + 1 == total {
+                info!(processed = index + 1, total, "Processed source files");
+            }
+        }
+
+        robocopy_logs_parser::write_results(&results, &config.output_file, config.overwrite)?;
+
+        if config.strict {
+            let diagnostic_count: usize = results.iter().map(|r| r.diagnostics().len()).sum();
+            if diagnostic_count > 0 {
+                return Err(anyhow!(
+                    "{} parse diagnostics encountered in strict mode",
+                    diagnostic_count
+                ));
+            }
+        }
+
+        let failed_runs = results
+            .iter()
+            .filter(|r| matches!(r.outcome(), Some(outcome) if !outcome.ok()))
+            .count();
+        if failed_runs > 0 {
+            return Err(anyhow!(
+                "{} run(s) reported a Robocopy failure via their exit code",
+                failed_runs
+            ));
+        }
+
         Ok::<(), anyhow::Error>(())
     })();
 
@@ -60,4 +127,165 @@ fn work(config: &Config) {
         }
     };
     info!(duration, success, "Done");
+    success
+}
+
+/// Expand `source` into the list of files to parse.
+///
+/// `source` may be a single file, a directory (in which case every regular
+/// file directly inside it is processed), or a glob pattern such as
+/// `C:\logs\*.log`.
+fn collect_sources(source: &Path) -> Result<Vec<PathBuf>> {
+    if source.is_dir() {
+        return list_dir(source, |_| true);
+    }
+
+    let pattern = source.to_string_lossy();
+    if pattern.contains('*') || pattern.contains('?') {
+        let dir = source
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."));
+        let file_pattern = source
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or_default()
+            .to_string();
+        return list_dir(dir, |name| glob_match(&file_pattern, name));
+    }
+
+    Ok(vec![source.to_path_buf()])
+}
+
+/// List the regular files directly inside `dir` whose name matches `predicate`
+fn list_dir(dir: &Path, predicate: impl Fn(&str) -> bool) -> Result<Vec<PathBuf>> {
+    let mut paths: Vec<PathBuf> = std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read directory {:?}", dir))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .map(&predicate)
+                .unwrap_or(false)
+        })
+        .collect();
+    paths.sort();
+    Ok(paths)
+}
+
+/// Minimal glob matcher supporting `*` (any run of characters) and `?` (any single character)
+fn glob_match(pattern: &str, text: &str) -> bool {
+    fn helper(pattern: &[char], text: &[char]) -> bool {
+        match pattern.first() {
+            None => text.is_empty(),
+            Some('*') => {
+                helper(&pattern[1..], text) || (!text.is_empty() && helper(pattern, &text[1..]))
+            }
+            Some('?') => !text.is_empty() && helper(&pattern[1..], &text[1..]),
+            Some(c) => text.first() == Some(c) && helper(&pattern[1..], &text[1..]),
+        }
+    }
+
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    helper(&pattern, &text)
+}
+
+/// Whether a parsed result falls within the `[since, until]` window, based on
+/// its start time (falling back to the end time if the run has no start time)
+fn in_window(
+    result: &RobocopyResult,
+    since: Option<DateTime<Local>>,
+    until: Option<DateTime<Local>>,
+) -> bool {
+    let Some(timestamp) = result.started().or_else(|| result.ended()) else {
+        return true;
+    };
+    if let Some(since) = since {
+        if timestamp < since {
+            return false;
+        }
+    }
+    if let Some(until) = until {
+        if timestamp > until {
+            return false;
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use robocopy_logs_parser::FilesMode;
+
+    #[test]
+    fn glob_match_supports_star_and_question_mark() {
+        assert!(glob_match("*.log", "robocopy.log"));
+        assert!(!glob_match("*.log", "robocopy.txt"));
+        assert!(glob_match("log?.txt", "log1.txt"));
+        assert!(!glob_match("log?.txt", "log12.txt"));
+        assert!(glob_match("*", "anything"));
+    }
+
+    // A log whose header has an unrecognized key, so it parses successfully
+    // but carries one diagnostic
+    const LOG_WITH_AN_UNKNOWN_HEADER_KEY: &str = "\
+-------------------------------------------------------------------------------
+   ROBOCOPY     ::     Robust File Copy for Windows
+-------------------------------------------------------------------------------
+
+   Bogus : whatever
+-------------------------------------------------------------------------------
+-------------------------------------------------------------------------------
+    Ended : Monday, January 5, 2026 10:00:10 AM
+";
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("robocopy-main-test-{}-{}", Uuid::new_v4(), name))
+    }
+
+    fn config_for(source: PathBuf, output_file: PathBuf, strict: bool) -> Config {
+        Config {
+            source,
+            output_file,
+            log_file: None,
+            overwrite: true,
+            since: None,
+            until: None,
+            files: FilesMode::None,
+            strict,
+            exit_code: None,
+            log_max_bytes: 10 * 1024 * 1024,
+            log_keep: 5,
+        }
+    }
+
+    #[test]
+    fn strict_mode_fails_the_run_when_diagnostics_are_present() {
+        let source = temp_path("source.log");
+        let output = temp_path("output.json");
+        std::fs::write(&source, LOG_WITH_AN_UNKNOWN_HEADER_KEY).unwrap();
+
+        let success = work(&config_for(source.clone(), output.clone(), true));
+
+        std::fs::remove_file(&source).ok();
+        std::fs::remove_file(&output).ok();
+        assert!(!success);
+    }
+
+    #[test]
+    fn non_strict_mode_succeeds_despite_diagnostics() {
+        let source = temp_path("source.log");
+        let output = temp_path("output.json");
+        std::fs::write(&source, LOG_WITH_AN_UNKNOWN_HEADER_KEY).unwrap();
+
+        let success = work(&config_for(source.clone(), output.clone(), false));
+
+        std::fs::remove_file(&source).ok();
+        std::fs::remove_file(&output).ok();
+        assert!(success);
+    }
 }
@@ -0,0 +1,44 @@
+//! Exercises `robocopy_logs_parser` the way an external crate would: through
+//! its public API only, parsing a log from an in-memory buffer rather than
+//! a file on disk.
+
+use robocopy_logs_parser::{parse_datetime, FilesMode, RobocopyResult};
+use std::io::Cursor;
+
+const SAMPLE_LOG: &str = "\
+-------------------------------------------------------------------------------
+   ROBOCOPY     ::     Robust File Copy for Windows
+-------------------------------------------------------------------------------
+
+  Started : Monday, January 5, 2026 10:00:00 AM
+   Source : C:\\src\\
+     Dest : C:\\dst\\
+-------------------------------------------------------------------------------
+   New File          1234    C:\\src\\a.txt
+-------------------------------------------------------------------------------
+    Ended : Monday, January 5, 2026 10:00:10 AM
+";
+
+#[test]
+fn from_reader_parses_an_in_memory_log_without_touching_the_filesystem() {
+    let result = RobocopyResult::from_reader(Cursor::new(SAMPLE_LOG.as_bytes())).unwrap();
+    assert!(result.started().is_some());
+    assert!(result.ended().is_some());
+    assert_eq!(result.diagnostics().len(), 0);
+}
+
+#[test]
+fn retain_file_entries_and_files_mode_are_part_of_the_public_api() {
+    let mut result = RobocopyResult::from_reader(Cursor::new(SAMPLE_LOG.as_bytes())).unwrap();
+    result.retain_file_entries(FilesMode::None);
+    assert_eq!(
+        serde_json::to_value(&result).unwrap()["file_entries"],
+        serde_json::json!([])
+    );
+}
+
+#[test]
+fn parse_datetime_is_part_of_the_public_api() {
+    assert!(parse_datetime("2026-01-05T10:00:00Z").is_ok());
+    assert!(parse_datetime("Monday, January 5, 2026 10:00:00 AM").is_ok());
+}